@@ -1,4 +1,5 @@
-use regex::Regex;
+use rayon::prelude::*;
+use regex::bytes::Regex;
 use std::collections::HashMap;
 use std::env;
 use std::error::Error;
@@ -7,8 +8,18 @@ use std::fs::File;
 use std::fs::OpenOptions;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::path::Path;
+use std::process::Child;
+use std::process::ChildStdout;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
+const DEFAULT_EXT: &str = "txt";
+
 #[derive(Debug)]
 struct BobError {
     text: String,
@@ -52,8 +63,9 @@ fn main() {
     //println!("{:?}", search_term);
 
     let ext = flags["ext"];
-    let extensions: Vec<Regex>;
-    let result = extensions_factory(ext);
+    let glob = flags["glob"];
+    let extensions: regex::RegexSet;
+    let result = file_matcher_factory(ext, glob);
     match result {
         Ok(v) => extensions = v,
         Err(e) => {
@@ -63,18 +75,80 @@ fn main() {
     }
     //println!("{:?}", extensions);
 
+    let threads = flags["threads"].parse::<usize>().unwrap_or(0);
+    let threads = if threads == 0 {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    } else {
+        threads
+    };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap();
+
+    let command = command_factory(flags["exec"]);
+    let decompress = flags["decompress"] == "y" || flags["decompress"] == "Y";
+
+    let size_filter: Option<SizeFilter>;
+    let result = size_filter_factory(flags["size"]);
+    match result {
+        Ok(v) => size_filter = v,
+        Err(e) => {
+            log(&format!("{}", e.text));
+            return;
+        }
+    }
+
+    let time_filters: Vec<TimeFilter>;
+    let result = time_filter_factory(flags["changed-within"], flags["changed-before"]);
+    match result {
+        Ok(v) => time_filters = v,
+        Err(e) => {
+            log(&format!("{}", e.text));
+            return;
+        }
+    }
+
+    let lines = flags["lines"] == "y" || flags["lines"] == "Y";
+    if lines && command.is_some() {
+        log(&format!(
+            "{}",
+            "lines=y can't be combined with exec=, since exec runs per file rather than per line"
+        ));
+        return;
+    }
+
+    let options = SearchOptions {
+        extensions,
+        command,
+        decompress,
+        size_filter,
+        time_filters,
+        lines,
+    };
+
     let root = flags["root"];
-    search(&search_term, &root, &extensions, &log);
+    pool.install(|| search(&search_term, &root, &options, &log));
 }
 
 fn define_flags<'a>() -> HashMap<&'a str, &'a str> {
     let mut flags = HashMap::new();
     flags.insert("term", ""); //The simple alphanumeric-only term you're searching for. Example: term="find me"
     flags.insert("root", "./"); //The starting folder for searching. Example root="c:/Looky Here"
-    flags.insert("ext", "txt"); //Up to 25 file extension(s) to search. Example -ext=txt,doc
+    flags.insert("ext", DEFAULT_EXT); //File extension(s) to search. Example -ext=txt,doc
+    flags.insert("glob", ""); //Shell-style filename glob pattern(s) to search, e.g. test_*.rs or data?.csv. Combines with ext=, unless ext= is txt (its default, whether left unset or given explicitly), in which case glob= is used on its own
     flags.insert("case", "n"); //y for case sensitive
     flags.insert("regexp", ""); //will search by regexp instead of term and case. Example regexp=(?i)^startswith
     flags.insert("log", "console"); //Where to log names of files containing the search. Can use "console". Example: -log=C:/Logs/Log.txt or -log=console
+    flags.insert("threads", "0"); //Number of threads to scan with. 0 means use the number of logical CPUs. Example: threads=4
+    flags.insert("exec", ""); //Run a command for each matching file instead of logging it. {} is the path, {/} the basename, {.} the path without extension. Example: exec="code {}"
+    flags.insert("decompress", "n"); //y to transparently search inside .gz, .bz2, .xz and .zst files
+    flags.insert("size", ""); //Only search files of this size. Example: size=+10k or size=-2M
+    flags.insert("changed-within", ""); //Only search files modified within this long ago, or since this date. Example: changed-within=2d or changed-within=2024-01-01
+    flags.insert("changed-before", ""); //Only search files last modified before this long ago, or before this date. Example: changed-before=3h or changed-before=2024-01-01
+    flags.insert("lines", "n"); //y to log every matching line as path:lineno:matched-line instead of just the path. Can't be combined with exec=
     flags
 }
 
@@ -83,10 +157,18 @@ fn print_helptext() {
     You must set either term or regexp
     The simple alphanumeric term you're searching for. Example: term="find me". Default is ""
     The starting folder for searching. Example root="c:/Looky Here". Default is ./
-    Up to 25 file extension(s) to search. Example -ext=txt,doc. Default is txt
+    File extension(s) to search. Example -ext=txt,doc. Default is txt
+    Shell-style filename glob pattern(s) to search, e.g. test_*.rs or data?.csv. Combines with ext= (a file is valid if it matches either), unless ext= is txt (its default, whether left unset or given explicitly), in which case glob= is used on its own. Default is ""
     y for case sensitive. Default is n
     Will search by regexp instead of term and case. Example regexp=(?i)^startswith. Default is ""
     Where to log names of files containing the search. Example: -log=C:/Logs/Log.txt Default is console
+    Number of threads to scan with. 0 means use the number of logical CPUs. Example: threads=4. Default is 0
+    Run a command for each matching file instead of logging it. {} is the path, {/} the basename, {.} the path without extension. Example: exec="code {}". Default is ""
+    y to transparently search inside .gz, .bz2, .xz and .zst files. Default is n
+    Only search files of this size. Example: size=+10k or size=-2M. Default is ""
+    Only search files modified within this long ago, or since this date. Example: changed-within=2d or changed-within=2024-01-01. Default is ""
+    Only search files last modified before this long ago, or before this date. Example: changed-before=3h or changed-before=2024-01-01. Default is ""
+    y to log every matching line as path:lineno:matched-line instead of just the path. Can't be combined with exec=. Default is n
     "#;
     print!("{}", help_text);
 }
@@ -119,7 +201,7 @@ fn has_required(flags: &HashMap<&str, &str>) -> bool {
 
 fn set_search_term(
     flags: &HashMap<&str, &str>,
-    logger: &dyn Fn(&str),
+    logger: &(dyn Fn(&str) + Send + Sync),
 ) -> Result<Regex, regex::Error> {
     let term = flags["term"];
     let case = flags["case"];
@@ -154,7 +236,7 @@ fn print_regerror(
     regexp: &str,
     case: &str,
     term: &str,
-    logger: &dyn Fn(&str),
+    logger: &(dyn Fn(&str) + Send + Sync),
 ) {
     if regexp != "" {
         logger(&format!("Problem regexp {} into regex {:?}", regexp, error));
@@ -166,17 +248,29 @@ fn print_regerror(
     }
 }
 
-fn extensions_factory(ext: &str) -> Result<Vec<Regex>, BobError> {
+//Combines the ext= and glob= patterns into a single RegexSet so a file is valid if it
+//matches either, built once up front instead of re-checked per file.
+//If glob= is given and ext= is the default "txt" (whether left unset or passed explicitly as
+//ext=txt), the ext patterns are skipped so glob= is used on its own instead of being unioned
+//with "txt".
+fn file_matcher_factory(ext: &str, glob: &str) -> Result<regex::RegexSet, BobError> {
+    let mut patterns = if ext == DEFAULT_EXT && !glob.is_empty() {
+        Vec::new()
+    } else {
+        extensions_factory(ext)
+    };
+    patterns.extend(glob_factory(glob));
+
+    regex::RegexSet::new(&patterns).map_err(|_| BobError {
+        text: format!("Failed to accept ext {} or glob {}", ext, glob),
+    })
+}
+
+fn extensions_factory(ext: &str) -> Vec<String> {
     //do case insensitive match for filename ending. Example: "(?i)\.txt$"
-    //maximum of 25 extensions
-    let raw_extensions: Vec<&str> = ext.splitn(26, ',').collect();
-    if raw_extensions.len() > 25 {
-        return Err(BobError {
-            text: "Surpassed 25 extensions".to_string(),
-        });
-    }
+    let raw_extensions: Vec<&str> = ext.split(',').collect();
 
-    let mut regexts: Vec<Regex> = Vec::with_capacity(raw_extensions.len());
+    let mut patterns: Vec<String> = Vec::with_capacity(raw_extensions.len());
     for raw_extension in raw_extensions.iter() {
         let mut raw_extension = raw_extension.to_string();
         if raw_extension.starts_with(".") {
@@ -184,71 +278,459 @@ fn extensions_factory(ext: &str) -> Result<Vec<Regex>, BobError> {
         } else {
             raw_extension = "(?i)\\.".to_string() + &raw_extension + "$";
         }
-        let reg_result = Regex::new(&raw_extension);
-        match reg_result {
-            Err(_) => {
-                return Err(BobError {
-                    text: format!("Failed to accept extension {}", &raw_extension),
-                })
-            }
-            Ok(v) => regexts.push(v),
+        patterns.push(raw_extension);
+    }
+
+    patterns
+}
+
+//Converts shell-style globs (test_*.rs, data?.csv) to anchored regexes the way MOROS does:
+//escape \ and ., translate * to .* and ? to ., then wrap as ^...$.
+fn glob_factory(glob: &str) -> Vec<String> {
+    if glob.is_empty() {
+        return Vec::new();
+    }
+    glob.split(',').map(|pattern| glob_to_regex(pattern)).collect()
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::with_capacity(glob.len() + 2);
+    pattern.push('^');
+    for c in glob.chars() {
+        match c {
+            '\\' => pattern.push_str("\\\\"),
+            '.' => pattern.push_str("\\."),
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push(c),
         }
     }
+    pattern.push('$');
+    format!("(?i){}", pattern)
+}
 
-    Ok(regexts.clone())
+//Bundles the scan-wide settings that used to be passed to search() one by one, so adding a
+//new flag doesn't mean adding another search() parameter.
+struct SearchOptions {
+    extensions: regex::RegexSet,
+    command: Option<CommandTemplate>,
+    decompress: bool,
+    size_filter: Option<SizeFilter>,
+    time_filters: Vec<TimeFilter>,
+    lines: bool,
 }
 
-fn search(search_reg: &Regex, root: &str, extensions: &Vec<Regex>, logger: &dyn Fn(&str)) {
-    for entry in WalkDir::new(&root).into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_file() {
-            if !is_valid_file(entry.file_name().to_str().unwrap(), extensions) {
-                continue;
-            }
-            if file_has_match(&entry, &search_reg) {
-                logger(&format!("{}", entry.path().to_str().unwrap()));
+fn search(
+    search_reg: &Regex,
+    root: &str,
+    options: &SearchOptions,
+    logger: &(dyn Fn(&str) + Send + Sync),
+) {
+    let entries: Vec<walkdir::DirEntry> = WalkDir::new(&root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .collect();
+
+    entries.into_par_iter().for_each(|entry| {
+        if !is_valid_file(entry.file_name().to_str().unwrap(), &options.extensions) {
+            return;
+        }
+        if !matches_filters(&entry, &options.size_filter, &options.time_filters) {
+            return;
+        }
+
+        //main() already rejects lines=y combined with exec=, so lines here always means
+        //command is None.
+        if options.lines {
+            log_matching_lines(&entry, &search_reg, options.decompress, logger);
+            return;
+        }
+
+        if file_has_match(&entry, &search_reg, options.decompress) {
+            let path = entry.path().to_str().unwrap();
+            match &options.command {
+                Some(command) => {
+                    let _ = command.generate(path).status();
+                }
+                None => logger(&format!("{}", path)),
             }
         }
+    });
+}
+
+fn is_valid_file(file_name: &str, extensions: &regex::RegexSet) -> bool {
+    extensions.is_match(file_name)
+}
+
+//Applied right after is_valid_file so oversized or stale files are skipped before they're
+//ever opened and read into memory.
+fn matches_filters(
+    entry: &walkdir::DirEntry,
+    size_filter: &Option<SizeFilter>,
+    time_filters: &Vec<TimeFilter>,
+) -> bool {
+    if size_filter.is_none() && time_filters.is_empty() {
+        return true;
     }
+
+    let metadata = match entry.metadata() {
+        Ok(v) => v,
+        Err(_) => return false,
+    };
+
+    if let Some(filter) = size_filter {
+        if !filter.is_within(metadata.len()) {
+            return false;
+        }
+    }
+
+    if !time_filters.is_empty() {
+        let modified = match metadata.modified() {
+            Ok(v) => v,
+            Err(_) => return false,
+        };
+        if !time_filters.iter().all(|filter| filter.is_within(modified)) {
+            return false;
+        }
+    }
+
+    true
 }
 
-fn is_valid_file(file_name: &str, extensions: &Vec<Regex>) -> bool {
-    for extension in extensions {
-        if extension.is_match(file_name) {
-            return true;
+#[derive(Debug, Clone, Copy)]
+enum SizeFilter {
+    AtLeast(u64),
+    AtMost(u64),
+    Equals(u64),
+}
+
+impl SizeFilter {
+    fn is_within(&self, size: u64) -> bool {
+        match self {
+            SizeFilter::AtLeast(n) => size >= *n,
+            SizeFilter::AtMost(n) => size <= *n,
+            SizeFilter::Equals(n) => size == *n,
         }
     }
-    false
 }
 
-fn file_has_match(entry: &walkdir::DirEntry, search_reg: &Regex) -> bool {
-    let file: File;
-    let result = File::open(entry.path());
-    match result {
-        Ok(v) => file = v,
-        Err(_) => return false,
+//Mirrors fd's SizeFilter. Accepts "+10k", "-2M" or a bare "500" (bytes), where k/M/G are
+//powers of 1024, '+' means at least, '-' means at most, and no sign means an exact match.
+fn size_filter_factory(expr: &str) -> Result<Option<SizeFilter>, BobError> {
+    if expr.is_empty() {
+        return Ok(None);
     }
-    let mut buf_reader = BufReader::new(file);
-    let mut contents = String::new();
-    let result = buf_reader.read_to_string(&mut contents);
-    match result {
-        Ok(_) => search_reg.is_match(&contents),
-        Err(_) => false,
+
+    let bad_expr = || BobError {
+        text: format!("Failed to parse size expression {}", expr),
+    };
+
+    let (sign, rest) = if let Some(rest) = expr.strip_prefix('+') {
+        (1i8, rest)
+    } else if let Some(rest) = expr.strip_prefix('-') {
+        (-1i8, rest)
+    } else {
+        (0i8, expr)
+    };
+
+    let split_at = rest.find(|c: char| c.is_alphabetic()).unwrap_or(rest.len());
+    let (digits, suffix) = rest.split_at(split_at);
+    let base: u64 = digits.parse().map_err(|_| bad_expr())?;
+    let multiplier: u64 = match suffix.to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" => 1024,
+        "m" => 1024 * 1024,
+        "g" => 1024 * 1024 * 1024,
+        _ => return Err(bad_expr()),
+    };
+    let bytes = base * multiplier;
+
+    Ok(Some(match sign {
+        1 => SizeFilter::AtLeast(bytes),
+        -1 => SizeFilter::AtMost(bytes),
+        _ => SizeFilter::Equals(bytes),
+    }))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TimeFilter {
+    Within(SystemTime),
+    Before(SystemTime),
+}
+
+impl TimeFilter {
+    fn is_within(&self, modified: SystemTime) -> bool {
+        match self {
+            TimeFilter::Within(cutoff) => modified >= *cutoff,
+            TimeFilter::Before(cutoff) => modified <= *cutoff,
+        }
+    }
+}
+
+//Mirrors fd's TimeFilter. changed-within keeps files modified at or after the cutoff,
+//changed-before keeps files modified at or before it.
+fn time_filter_factory(within: &str, before: &str) -> Result<Vec<TimeFilter>, BobError> {
+    let mut filters = Vec::new();
+    if !within.is_empty() {
+        filters.push(TimeFilter::Within(parse_time_expr(within)?));
+    }
+    if !before.is_empty() {
+        filters.push(TimeFilter::Before(parse_time_expr(before)?));
+    }
+    Ok(filters)
+}
+
+//Accepts a duration relative to now (e.g. "2d", "3h") or an absolute "YYYY-MM-DD" date.
+fn parse_time_expr(expr: &str) -> Result<SystemTime, BobError> {
+    if let Ok(duration) = humantime::parse_duration(expr) {
+        return Ok(SystemTime::now() - duration);
+    }
+
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(expr, "%Y-%m-%d") {
+        let timestamp = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+        return Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp.max(0) as u64));
+    }
+
+    Err(BobError {
+        text: format!("Failed to parse time expression {}", expr),
+    })
+}
+
+//Reads raw bytes rather than read_to_string so Latin-1 or binary-ish files that aren't valid
+//UTF-8 are still matched instead of being silently skipped.
+fn file_has_match(entry: &walkdir::DirEntry, search_reg: &Regex, decompress: bool) -> bool {
+    match read_file_bytes(entry, decompress) {
+        Some(contents) => search_reg.is_match(&contents),
+        None => false,
+    }
+}
+
+//grep-style output for lines=y: every matching line is logged as path:lineno:matched-line,
+//with the matched bytes lossily decoded for display.
+fn log_matching_lines(
+    entry: &walkdir::DirEntry,
+    search_reg: &Regex,
+    decompress: bool,
+    logger: &(dyn Fn(&str) + Send + Sync),
+) -> bool {
+    let contents = match read_file_bytes(entry, decompress) {
+        Some(v) => v,
+        None => return false,
+    };
+
+    let path = entry.path().to_str().unwrap();
+    let mut matched = false;
+    for (index, line) in contents.split(|&b| b == b'\n').enumerate() {
+        if search_reg.is_match(line) {
+            matched = true;
+            logger(&format!(
+                "{}:{}:{}",
+                path,
+                index + 1,
+                String::from_utf8_lossy(line)
+            ));
+        }
+    }
+    matched
+}
+
+fn read_file_bytes(entry: &walkdir::DirEntry, decompress: bool) -> Option<Vec<u8>> {
+    let mut reader: Box<dyn Read> = if decompress {
+        match decompressor_for(entry.path()) {
+            Some(v) => v,
+            None => open_plain_file(entry.path())?,
+        }
+    } else {
+        open_plain_file(entry.path())?
+    };
+
+    let mut contents = Vec::new();
+    reader.read_to_end(&mut contents).ok()?;
+    Some(contents)
+}
+
+fn open_plain_file(path: &Path) -> Option<Box<dyn Read>> {
+    let file = File::open(path).ok()?;
+    Some(Box::new(BufReader::new(file)))
+}
+
+//Transparent decompression like ripgrep's DecompressionReader: picks a decompressor based on
+//the file extension and spawns it as a child process, streaming its stdout through a BufReader.
+//Falls back to None (the caller reads the raw file) if the extension is unrecognized or the
+//decompressor binary isn't installed.
+fn decompressor_for(path: &Path) -> Option<Box<dyn Read>> {
+    let file_name = path.file_name()?.to_str()?;
+    let program = if file_name.ends_with(".gz") {
+        "gzip"
+    } else if file_name.ends_with(".bz2") {
+        "bzip2"
+    } else if file_name.ends_with(".xz") {
+        "xz"
+    } else if file_name.ends_with(".zst") {
+        "zstd"
+    } else {
+        return None;
+    };
+
+    let mut child = Command::new(program)
+        .arg("-d")
+        .arg("-c")
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let stdout = child.stdout.take()?;
+    Some(Box::new(DecompressingReader {
+        child,
+        stdout: BufReader::new(stdout),
+    }))
+}
+
+//Owns the decompressor Child alongside its stdout, like ripgrep's DecompressionReader, so the
+//process is wait()ed on drop instead of left as an unreaped zombie once the pipe is taken.
+struct DecompressingReader {
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Read for DecompressingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.stdout.read(buf)
+    }
+}
+
+impl Drop for DecompressingReader {
+    fn drop(&mut self) {
+        let _ = self.child.wait();
+    }
+}
+
+//Modeled on fd's CommandTemplate: the exec= template is tokenized once into placeholder/literal
+//tokens per whitespace-separated word, so substitution per match is just a cheap string build.
+#[derive(Debug, Clone)]
+struct CommandTemplate {
+    words: Vec<Vec<Token>>,
+}
+
+#[derive(Debug, Clone)]
+enum Token {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+#[derive(Debug, Clone)]
+enum Placeholder {
+    Path,
+    Basename,
+    NoExt,
+}
+
+impl CommandTemplate {
+    fn generate(&self, path: &str) -> Command {
+        let path_obj = Path::new(path);
+        let basename = path_obj
+            .file_name()
+            .map(|f| f.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string());
+        let no_ext = path_obj.with_extension("").to_string_lossy().into_owned();
+
+        let args: Vec<String> = self
+            .words
+            .iter()
+            .map(|word| {
+                word.iter()
+                    .map(|token| match token {
+                        Token::Text(s) => s.clone(),
+                        Token::Placeholder(Placeholder::Path) => path.to_string(),
+                        Token::Placeholder(Placeholder::Basename) => basename.clone(),
+                        Token::Placeholder(Placeholder::NoExt) => no_ext.clone(),
+                    })
+                    .collect::<String>()
+            })
+            .collect();
+
+        let mut command = Command::new(&args[0]);
+        command.args(&args[1..]);
+        command
+    }
+}
+
+fn command_factory(exec: &str) -> Option<CommandTemplate> {
+    let words: Vec<Vec<Token>> = exec.split_whitespace().map(tokenize_word).collect();
+    //A whitespace-only (or empty) exec= tokenizes to no words; CommandTemplate::generate
+    //would index args[0] on an empty Vec and panic, so treat it as "no command" instead.
+    if words.is_empty() {
+        return None;
+    }
+    Some(CommandTemplate { words })
+}
+
+fn tokenize_word(word: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            literal.push(c);
+            continue;
+        }
+        let mut placeholder = String::new();
+        while let Some(&next) = chars.peek() {
+            if next == '}' {
+                chars.next();
+                break;
+            }
+            placeholder.push(next);
+            chars.next();
+        }
+        let token = match placeholder.as_str() {
+            "" => Some(Token::Placeholder(Placeholder::Path)),
+            "/" => Some(Token::Placeholder(Placeholder::Basename)),
+            "." => Some(Token::Placeholder(Placeholder::NoExt)),
+            _ => None,
+        };
+        match token {
+            Some(token) => {
+                if !literal.is_empty() {
+                    tokens.push(Token::Text(literal.clone()));
+                    literal.clear();
+                }
+                tokens.push(token);
+            }
+            None => {
+                literal.push('{');
+                literal.push_str(&placeholder);
+                literal.push('}');
+            }
+        }
+    }
+    if !literal.is_empty() {
+        tokens.push(Token::Text(literal));
     }
+    tokens
 }
 
 //log_name String is moved into log_factory so a reference to it can be used in OpenOptions.open.
 //The file appender function needs "move" so it can own the log_file value,
 //otherwise it causes lifetime issues.
-fn log_factory<'a>(log_name: String) -> Box<dyn Fn(&str)> {
+//The file handle is opened once and wrapped in a Mutex so concurrent writes from the
+//rayon thread pool don't interleave.
+fn log_factory<'a>(log_name: String) -> Box<dyn Fn(&str) + Send + Sync> {
     if log_name == "console" {
-        return Box::new(|msg: &str| print!("{}", msg));
+        return Box::new(|msg: &str| println!("{}", msg));
     } else {
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&log_name)
+            .unwrap();
+        let file = Mutex::new(file);
         return Box::new(move |msg: &str| {
-            let mut file = OpenOptions::new()
-                .append(true)
-                .create(true)
-                .open(&log_name)
-                .unwrap();
+            let mut file = file.lock().unwrap();
             file.write_all(msg.as_bytes()).unwrap();
             file.write_all("\n".as_bytes()).unwrap();
         });